@@ -0,0 +1,133 @@
+use crate::measurement::{calc_current, decode_sum, encode_bus, encode_shunt, encode_sum};
+use crate::{Channel, Error, I2c, Ina3221, Register};
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Writes a limit in shunt voltage format, in microvolt(uV).
+    ///
+    /// The value is rounded towards zero to a multiple of 40uV and clamped to the register range.
+    #[inline]
+    fn write_shunt_volt(&mut self, reg: Register, value: i32) -> Result<(), Error> {
+        self.write_u16(reg, encode_shunt(value))
+    }
+
+    /// Writes a limit in bus voltage format, in milivolt(mV).
+    ///
+    /// The value is rounded towards zero to a multiple of 8mV and clamped to the register range.
+    #[inline]
+    fn write_bus_volt(&mut self, reg: Register, value: i32) -> Result<(), Error> {
+        self.write_u16(reg, encode_bus(value))
+    }
+
+    /// Critical alert limit of a channel, in microvolt(uV).
+    pub fn critical_limit(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.read_shunt_volt(channel.critical_limit_register())
+    }
+
+    /// Sets critical alert limit of a channel, in microvolt(uV).
+    pub fn set_critical_limit(&mut self, channel: Channel, value: i32) -> Result<(), Error> {
+        self.write_shunt_volt(channel.critical_limit_register(), value)
+    }
+
+    /// Warning alert limit of a channel, in microvolt(uV).
+    pub fn warning_limit(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.read_shunt_volt(channel.warning_limit_register())
+    }
+
+    /// Sets warning alert limit of a channel, in microvolt(uV).
+    pub fn set_warning_limit(&mut self, channel: Channel, value: i32) -> Result<(), Error> {
+        self.write_shunt_volt(channel.warning_limit_register(), value)
+    }
+
+    /// Converts a current(mA) to the shunt voltage(uV) of a channel.
+    #[inline]
+    fn current_to_shunt_volt(&self, channel: Channel, value: i32) -> i32 {
+        let voltage = value as i64 * self.shunt_resistor(channel) as i64 / 1000;
+        voltage.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    /// Critical alert limit of a channel as current, in milli-Amp(mA).
+    ///
+    /// Calculated based on the resistor value provided.
+    pub fn critical_current_limit(&mut self, channel: Channel) -> Result<i32, Error> {
+        let voltage = self.critical_limit(channel)?;
+        Ok(calc_current(voltage, self.shunt_resistor(channel)))
+    }
+
+    /// Sets critical alert limit of a channel as current, in milli-Amp(mA).
+    ///
+    /// Converted to shunt voltage based on the resistor value provided.
+    pub fn set_critical_current_limit(
+        &mut self,
+        channel: Channel,
+        value: i32,
+    ) -> Result<(), Error> {
+        let voltage = self.current_to_shunt_volt(channel, value);
+        self.set_critical_limit(channel, voltage)
+    }
+
+    /// Warning alert limit of a channel as current, in milli-Amp(mA).
+    ///
+    /// Calculated based on the resistor value provided.
+    pub fn warning_current_limit(&mut self, channel: Channel) -> Result<i32, Error> {
+        let voltage = self.warning_limit(channel)?;
+        Ok(calc_current(voltage, self.shunt_resistor(channel)))
+    }
+
+    /// Sets warning alert limit of a channel as current, in milli-Amp(mA).
+    ///
+    /// Converted to shunt voltage based on the resistor value provided.
+    pub fn set_warning_current_limit(&mut self, channel: Channel, value: i32) -> Result<(), Error> {
+        let voltage = self.current_to_shunt_volt(channel, value);
+        self.set_warning_limit(channel, voltage)
+    }
+
+    /// Sum of the shunt voltages of the channels selected for summation, in microvolt(uV).
+    ///
+    /// See [`Ina3221::set_summation_channels`].
+    pub fn shunt_sum(&mut self) -> Result<i32, Error> {
+        Ok(decode_sum(self.read_u16(Register::ShuntVoltageSum)?))
+    }
+
+    /// Shunt voltage sum limit, in microvolt(uV).
+    pub fn shunt_sum_limit(&mut self) -> Result<i32, Error> {
+        Ok(decode_sum(self.read_u16(Register::ShuntVoltageSumLimit)?))
+    }
+
+    /// Sets shunt voltage sum limit, in microvolt(uV).
+    ///
+    /// The value is rounded towards zero to a multiple of 40uV and clamped to the register range.
+    pub fn set_shunt_sum_limit(&mut self, value: i32) -> Result<(), Error> {
+        self.write_u16(Register::ShuntVoltageSumLimit, encode_sum(value))
+    }
+
+    /// Power-valid upper limit, in milivolt(mV).
+    pub fn power_valid_upper_limit(&mut self) -> Result<i32, Error> {
+        self.read_bus_volt(Register::PowerValidUpperLimit)
+    }
+
+    /// Sets power-valid upper limit, in milivolt(mV).
+    ///
+    /// The power-valid output goes high when all bus voltages exceed this limit.
+    pub fn set_power_valid_upper_limit(&mut self, value: i32) -> Result<(), Error> {
+        self.write_bus_volt(Register::PowerValidUpperLimit, value)
+    }
+
+    /// Power-valid lower limit, in milivolt(mV).
+    pub fn power_valid_lower_limit(&mut self) -> Result<i32, Error> {
+        self.read_bus_volt(Register::PowerValidLowerLimit)
+    }
+
+    /// Sets power-valid lower limit, in milivolt(mV).
+    ///
+    /// The power-valid output goes low when any bus voltage drops below this limit.
+    pub fn set_power_valid_lower_limit(&mut self, value: i32) -> Result<(), Error> {
+        self.write_bus_volt(Register::PowerValidLowerLimit, value)
+    }
+
+    /// Reads the power-valid flag.
+    ///
+    /// This reads the Mask/Enable register, so other flags will be cleared.
+    pub fn power_valid(&mut self) -> Result<bool, Error> {
+        Ok(self.flags()?.power_valid())
+    }
+}
@@ -0,0 +1,65 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{Error as PinError, InputPin};
+
+use crate::{Channel, Error, I2c, Ina3221, MaskEnable};
+
+/// Interval between polls of the alert pin, in microsecond(us).
+const POLL_INTERVAL_US: u32 = 500;
+
+impl MaskEnable {
+    /// Channels whose critical alert flag is set.
+    pub fn critical_channels(&self) -> impl Iterator<Item = Channel> + '_ {
+        Channel::ALL
+            .into_iter()
+            .filter(|channel| self.critical(*channel))
+    }
+
+    /// Channels whose warning alert flag is set.
+    pub fn warning_channels(&self) -> impl Iterator<Item = Channel> + '_ {
+        Channel::ALL
+            .into_iter()
+            .filter(|channel| self.warning(*channel))
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Polls the alert pin until it goes low, then reads the flags.
+    ///
+    /// Works with the critical, warning and power-valid pins, which are all
+    /// open-drain outputs pulled low on alert. Use [`MaskEnable::critical_channels`]
+    /// and [`MaskEnable::warning_channels`] to find out which channel tripped.
+    ///
+    /// Returns [`Error::Timeout`] if the pin stays high for `timeout_us` microseconds.
+    /// With `None` this blocks until an alert occurs, which may be never.
+    /// This reads the Mask/Enable register, so latched flags will be cleared.
+    pub fn wait_alert<P: InputPin, D: DelayNs>(
+        &mut self,
+        pin: &mut P,
+        delay: &mut D,
+        timeout_us: Option<u32>,
+    ) -> Result<MaskEnable, Error> {
+        let mut elapsed: u32 = 0;
+        while pin.is_high().map_err(|e| Error::PinError(e.kind()))? {
+            if timeout_us.is_some_and(|timeout| elapsed >= timeout) {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            elapsed = elapsed.saturating_add(POLL_INTERVAL_US);
+        }
+        self.flags()
+    }
+
+    /// Waits until the alert pin goes low, then reads the flags.
+    ///
+    /// See [`Ina3221::wait_alert`].
+    #[cfg(feature = "async")]
+    pub async fn wait_alert_async<P: embedded_hal_async::digital::Wait>(
+        &mut self,
+        pin: &mut P,
+    ) -> Result<MaskEnable, Error> {
+        pin.wait_for_low()
+            .await
+            .map_err(|e| Error::PinError(e.kind()))?;
+        self.flags()
+    }
+}
@@ -0,0 +1,153 @@
+use core::fmt;
+
+use num_enum::FromPrimitive;
+
+use crate::general::{AveragingMode, ConversionTime, OperatingMode};
+use crate::mask::fmt_channels;
+use crate::{Channel, Error, I2c, Ina3221};
+
+/// Whole-device configuration, the content of the configuration register(0x00).
+///
+/// The default value is the power-on state of the chip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub mode: OperatingMode,
+    pub averaging: AveragingMode,
+    pub shunt_conversion_time: ConversionTime,
+    pub bus_conversion_time: ConversionTime,
+    /// Channel enables, indexed by [`Channel::index`].
+    pub channels: [bool; 3],
+}
+
+/// Time of one full averaged conversion cycle of shunt and bus voltages, in microsecond(us).
+///
+/// That is the time between updates of the measurement registers in continuous
+/// mode, or until a one-shot conversion is ready. Pass `None` for a conversion
+/// the operating mode skips. Channel enables are indexed by [`Channel::index`].
+pub const fn conversion_time_us(
+    averaging: AveragingMode,
+    shunt_conversion_time: Option<ConversionTime>,
+    bus_conversion_time: Option<ConversionTime>,
+    channels: [bool; 3],
+) -> u32 {
+    let enabled_channels = channels[0] as u32 + channels[1] as u32 + channels[2] as u32;
+    let mut per_sample = 0;
+    if let Some(time) = shunt_conversion_time {
+        per_sample += time.micros() as u32;
+    }
+    if let Some(time) = bus_conversion_time {
+        per_sample += time.micros() as u32;
+    }
+    averaging.samples() as u32 * enabled_channels * per_sample
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mode: OperatingMode::ContinuousShuntBus,
+            averaging: AveragingMode::Samples1,
+            shunt_conversion_time: ConversionTime::T1100us,
+            bus_conversion_time: ConversionTime::T1100us,
+            channels: [true; 3],
+        }
+    }
+}
+
+impl Config {
+    pub fn from_bits(bits: u16) -> Self {
+        Self {
+            mode: OperatingMode::from_primitive((bits & 0b111) as u8),
+            shunt_conversion_time: ConversionTime::from_primitive((bits >> 3 & 0b111) as u8),
+            bus_conversion_time: ConversionTime::from_primitive((bits >> 6 & 0b111) as u8),
+            averaging: AveragingMode::from_primitive((bits >> 9 & 0b111) as u8),
+            channels: Channel::ALL.map(|channel| bits >> channel.enable_bit() & 1 == 1),
+        }
+    }
+
+    pub fn bits(&self) -> u16 {
+        let mut bits = u8::from(self.mode) as u16
+            | (u8::from(self.shunt_conversion_time) as u16) << 3
+            | (u8::from(self.bus_conversion_time) as u16) << 6
+            | (u8::from(self.averaging) as u16) << 9;
+        for channel in Channel::ALL {
+            bits |= (self.channels[channel.index()] as u16) << channel.enable_bit();
+        }
+        bits
+    }
+
+    /// Time of one full averaged conversion cycle with this configuration, in microsecond(us).
+    ///
+    /// Only the conversions selected by the operating mode are counted.
+    pub fn conversion_time_us(&self) -> u32 {
+        let (shunt, bus) = match self.mode {
+            OperatingMode::PowerDown => (None, None),
+            OperatingMode::OneshotShunt | OperatingMode::ContinuousShunt => {
+                (Some(self.shunt_conversion_time), None)
+            }
+            OperatingMode::OneshotBus | OperatingMode::ContinuousBus => {
+                (None, Some(self.bus_conversion_time))
+            }
+            OperatingMode::OneshotShuntBus | OperatingMode::ContinuousShuntBus => (
+                Some(self.shunt_conversion_time),
+                Some(self.bus_conversion_time),
+            ),
+        };
+        conversion_time_us(self.averaging, shunt, bus, self.channels)
+    }
+
+    pub fn channel_enabled(&self, channel: Channel) -> bool {
+        self.channels[channel.index()]
+    }
+
+    pub fn set_channel_enabled(&mut self, channel: Channel, enabled: bool) {
+        self.channels[channel.index()] = enabled;
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "avg={}, shunt CT={}, bus CT={}, ",
+            self.averaging, self.shunt_conversion_time, self.bus_conversion_time,
+        )?;
+        fmt_channels(f, self.channels)?;
+        write!(f, " enabled, {}", self.mode)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Config {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "avg={}, shunt CT={}, bus CT={}, ch1={=bool} ch2={=bool} ch3={=bool}, {}",
+            self.averaging,
+            self.shunt_conversion_time,
+            self.bus_conversion_time,
+            self.channels[0],
+            self.channels[1],
+            self.channels[2],
+            self.mode,
+        )
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Writes the whole configuration in a single I2C transaction.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.write_config_reg(config.bits())
+    }
+
+    /// Time of one full averaged conversion cycle with the current configuration, in microsecond(us).
+    ///
+    /// Useful to schedule reads in continuous mode. See [`Config::conversion_time_us`].
+    pub fn measurement_interval_us(&mut self) -> Result<u32, Error> {
+        Ok(self.read_config()?.conversion_time_us())
+    }
+
+    /// Reads and decodes the whole configuration.
+    pub fn read_config(&mut self) -> Result<Config, Error> {
+        Ok(Config::from_bits(self.read_config_reg()?))
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Channel, I2c, Ina3221, Measurements, MicroOhms};
+
+/// A * us (uV * us / uOhm) per uAh.
+const CHARGE_PER_MICRO_HOUR: i64 = 3_600;
+/// mW * us (mV * uV * us / uOhm) per uWh.
+const ENERGY_PER_MICRO_HOUR: i128 = 3_600_000;
+
+/// Software charge and energy accumulator for all 3 channels.
+///
+/// The INA3221 has no hardware accumulator, so feed this periodically with
+/// measurements, e.g. from [`crate::Ina3221::read_all`], and the time elapsed
+/// since the previous update. Each measurement is taken as constant over the
+/// elapsed time.
+///
+/// Integration is done on the raw shunt voltage, not on the truncated
+/// `current` and `power` fields. Internally charge is accumulated in
+/// uV * us and energy in mV * uV * us with saturating arithmetic, and only
+/// divided by the shunt resistance when read, so small currents still add up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EnergyMeter {
+    resistors: [MicroOhms; 3],
+    charge: [i64; 3],
+    energy: [i128; 3],
+    elapsed_us: u64,
+    last_timestamp_us: Option<u64>,
+}
+
+impl EnergyMeter {
+    /// Creates a meter for the shunt resistors of channel 1, 2 and 3.
+    pub const fn new(resistors: [MicroOhms; 3]) -> Self {
+        Self {
+            resistors,
+            charge: [0; 3],
+            energy: [0; 3],
+            elapsed_us: 0,
+            last_timestamp_us: None,
+        }
+    }
+
+    /// Accumulates `measurements` over `elapsed_us` microseconds.
+    pub fn update(&mut self, measurements: &Measurements, elapsed_us: u32) {
+        for channel in Channel::ALL {
+            let index = channel.index();
+            let measurement = measurements[channel];
+            let charge = measurement.shunt_voltage as i64 * elapsed_us as i64;
+            let energy = measurement.bus_voltage as i128 * charge as i128;
+            self.charge[index] = self.charge[index].saturating_add(charge);
+            self.energy[index] = self.energy[index].saturating_add(energy);
+        }
+        self.elapsed_us = self.elapsed_us.saturating_add(elapsed_us as u64);
+    }
+
+    /// Accumulates `measurements` taken at `timestamp_us`, a monotonic time in microseconds.
+    ///
+    /// The first call only records the timestamp.
+    pub fn update_at(&mut self, measurements: &Measurements, timestamp_us: u64) {
+        if let Some(last) = self.last_timestamp_us {
+            let elapsed = timestamp_us.saturating_sub(last).min(u32::MAX as u64) as u32;
+            self.update(measurements, elapsed);
+        }
+        self.last_timestamp_us = Some(timestamp_us);
+    }
+
+    /// Clears the accumulated values, keeping the shunt resistors.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.resistors);
+    }
+
+    /// Total time accumulated, in microsecond(us).
+    pub const fn elapsed_us(&self) -> u64 {
+        self.elapsed_us
+    }
+
+    /// Charge of a channel, in micro-Amp-hour(uAh).
+    pub const fn charge_uah(&self, channel: Channel) -> i64 {
+        let resistor = self.resistors[channel.index()].get() as i64;
+        self.charge[channel.index()] / (resistor * CHARGE_PER_MICRO_HOUR)
+    }
+
+    /// Charge of a channel, in milli-Amp-hour(mAh).
+    pub const fn charge_mah(&self, channel: Channel) -> i64 {
+        self.charge_uah(channel) / 1000
+    }
+
+    /// Energy of a channel, in micro-Watt-hour(uWh).
+    pub const fn energy_uwh(&self, channel: Channel) -> i64 {
+        let resistor = self.resistors[channel.index()].get() as i128;
+        let energy = self.energy[channel.index()] / (resistor * ENERGY_PER_MICRO_HOUR);
+        if energy > i64::MAX as i128 {
+            i64::MAX
+        } else if energy < i64::MIN as i128 {
+            i64::MIN
+        } else {
+            energy as i64
+        }
+    }
+
+    /// Energy of a channel, in milli-Watt-hour(mWh).
+    pub const fn energy_mwh(&self, channel: Channel) -> i64 {
+        self.energy_uwh(channel) / 1000
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Creates an [`EnergyMeter`] with the shunt resistors of this device.
+    pub fn energy_meter(&self) -> EnergyMeter {
+        EnergyMeter::new([self.shunt_r1, self.shunt_r2, self.shunt_r3])
+    }
+}
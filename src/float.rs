@@ -0,0 +1,25 @@
+use crate::{Channel, Error, I2c, Ina3221};
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Shunt voltage of a channel, in volt(V).
+    pub fn shunt_voltage_v(&mut self, channel: Channel) -> Result<f32, Error> {
+        Ok(self.shunt_voltage(channel)? as f32 / 1_000_000.0)
+    }
+
+    /// Bus voltage of a channel, in volt(V).
+    pub fn bus_voltage_v(&mut self, channel: Channel) -> Result<f32, Error> {
+        Ok(self.bus_voltage(channel)? as f32 / 1_000.0)
+    }
+
+    /// Calculates current of a channel based on the resistor value provided, in Amp(A).
+    pub fn current_a(&mut self, channel: Channel) -> Result<f32, Error> {
+        // uV / micro-ohm = A
+        Ok(self.shunt_voltage(channel)? as f32 / self.shunt_resistor(channel) as f32)
+    }
+
+    /// Calculates load power of a channel based on the resistor value provided, in Watt(W).
+    pub fn power_w(&mut self, channel: Channel) -> Result<f32, Error> {
+        let current = self.current_a(channel)?;
+        Ok(self.bus_voltage_v(channel)? * current)
+    }
+}
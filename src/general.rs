@@ -1,10 +1,78 @@
-use crate::{Error, I2c, Ina3221};
+use crate::measurement::{calc_current, calc_power, decode_bus, decode_shunt};
+use crate::{Error, I2c, Ina3221, Register};
+
+use core::fmt;
 
 use num_enum::{FromPrimitive, IntoPrimitive};
 
+/// One of the 3 measurement channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Channel {
+    Ch1,
+    Ch2,
+    Ch3,
+}
+
+impl Channel {
+    /// All channels, in order.
+    pub const ALL: [Channel; 3] = [Channel::Ch1, Channel::Ch2, Channel::Ch3];
+
+    /// Zero-based index of the channel.
+    pub const fn index(&self) -> usize {
+        match self {
+            Channel::Ch1 => 0,
+            Channel::Ch2 => 1,
+            Channel::Ch3 => 2,
+        }
+    }
+
+    pub(crate) const fn shunt_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::ShuntVoltage1,
+            Channel::Ch2 => Register::ShuntVoltage2,
+            Channel::Ch3 => Register::ShuntVoltage3,
+        }
+    }
+
+    pub(crate) const fn bus_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::BusVoltage1,
+            Channel::Ch2 => Register::BusVoltage2,
+            Channel::Ch3 => Register::BusVoltage3,
+        }
+    }
+
+    pub(crate) const fn critical_limit_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::CriticalLimit1,
+            Channel::Ch2 => Register::CriticalLimit2,
+            Channel::Ch3 => Register::CriticalLimit3,
+        }
+    }
+
+    pub(crate) const fn warning_limit_register(&self) -> Register {
+        match self {
+            Channel::Ch1 => Register::WarningLimit1,
+            Channel::Ch2 => Register::WarningLimit2,
+            Channel::Ch3 => Register::WarningLimit3,
+        }
+    }
+
+    /// Bit of the channel enable flag in the configuration register.
+    pub(crate) const fn enable_bit(&self) -> u8 {
+        14 - self.index() as u8
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ch{}", self.index() + 1)
+    }
+}
+
 #[repr(u8)]
 #[derive(IntoPrimitive, FromPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OperatingMode {
     #[num_enum(default)]
     PowerDown = 0,
@@ -20,7 +88,6 @@ pub enum OperatingMode {
 
 #[repr(u8)]
 #[derive(IntoPrimitive, FromPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AveragingMode {
     #[num_enum(default)]
     Samples1,
@@ -35,7 +102,6 @@ pub enum AveragingMode {
 
 #[repr(u8)]
 #[derive(IntoPrimitive, FromPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ConversionTime {
     #[num_enum(default)]
     T140us,
@@ -48,158 +114,325 @@ pub enum ConversionTime {
     T8244us,
 }
 
+impl OperatingMode {
+    /// Human-readable name of the mode, e.g. "continuous shunt+bus".
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            OperatingMode::PowerDown => "power-down",
+            OperatingMode::OneshotShunt => "single-shot shunt",
+            OperatingMode::OneshotBus => "single-shot bus",
+            OperatingMode::OneshotShuntBus => "single-shot shunt+bus",
+            OperatingMode::ContinuousShunt => "continuous shunt",
+            OperatingMode::ContinuousBus => "continuous bus",
+            OperatingMode::ContinuousShuntBus => "continuous shunt+bus",
+        }
+    }
+}
+
+impl AveragingMode {
+    /// Number of samples averaged.
+    pub const fn samples(&self) -> u16 {
+        match self {
+            AveragingMode::Samples1 => 1,
+            AveragingMode::Samples4 => 4,
+            AveragingMode::Samples16 => 16,
+            AveragingMode::Samples64 => 64,
+            AveragingMode::Samples128 => 128,
+            AveragingMode::Samples256 => 256,
+            AveragingMode::Samples512 => 512,
+            AveragingMode::Samples1024 => 1024,
+        }
+    }
+}
+
+impl ConversionTime {
+    /// Conversion time in microseconds(us).
+    pub const fn micros(&self) -> u16 {
+        match self {
+            ConversionTime::T140us => 140,
+            ConversionTime::T204us => 204,
+            ConversionTime::T332us => 332,
+            ConversionTime::T588us => 588,
+            ConversionTime::T1100us => 1100,
+            ConversionTime::T2116us => 2116,
+            ConversionTime::T4156us => 4156,
+            ConversionTime::T8244us => 8244,
+        }
+    }
+
+    /// Human-readable conversion time with unit, e.g. "1.1 ms".
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ConversionTime::T140us => "140 us",
+            ConversionTime::T204us => "204 us",
+            ConversionTime::T332us => "332 us",
+            ConversionTime::T588us => "588 us",
+            ConversionTime::T1100us => "1.1 ms",
+            ConversionTime::T2116us => "2.116 ms",
+            ConversionTime::T4156us => "4.156 ms",
+            ConversionTime::T8244us => "8.244 ms",
+        }
+    }
+}
+
+impl fmt::Display for OperatingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for AveragingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.samples())
+    }
+}
+
+impl fmt::Display for ConversionTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for OperatingMode {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.as_str())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AveragingMode {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u16}", self.samples())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConversionTime {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.as_str())
+    }
+}
+
 impl<I2C: I2c> Ina3221<I2C> {
     /// Resets the chip, equivalent to power cycling the chip.
     /// 
     /// All registers will be set to default state.
     pub fn reset(&mut self) -> Result<(), Error> {
-        self.write_u16(0x00, 0x8000)
+        self.write_config_reg(0x8000)
     }
 
     /// gets current power mode
     pub fn power_mode(&mut self) -> Result<OperatingMode, Error> {
-        let mode = (self.read_u16(0x00)? & 0b111) as u8;
+        let mode = (self.read_config_reg()? & 0b111) as u8;
         Ok(OperatingMode::from_primitive(mode))
     }
 
     /// sets power mode
     pub fn set_power_mode(&mut self, value: OperatingMode) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
+        let original_state = self.read_config_reg()?;
         let new_value: u16 = value as u8 as u16;
-        let new_state = original_state & 0xFFFC | new_value;
-        self.write_u16(0x00, new_state)
+        let new_state = original_state & 0xFFF8 | new_value;
+        self.write_config_reg(new_state)
     }
 
     /// gets value averaging mode
     pub fn averaging_mode(&mut self) -> Result<AveragingMode, Error> {
-        let mode = (self.read_u16(0x00)? >> 9 & 0b111) as u8;
+        let mode = (self.read_config_reg()? >> 9 & 0b111) as u8;
         Ok(AveragingMode::from_primitive(mode))
     }
 
     /// sets value averaging mode
     pub fn set_averaging_mode(&mut self, value: AveragingMode) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
+        let original_state = self.read_config_reg()?;
         let new_value: u16 = value as u8 as u16;
         let new_state = original_state & 0xF1FF | new_value << 9;
-        self.write_u16(0x00, new_state)
+        self.write_config_reg(new_state)
+    }
+
+    /// gets shunt voltage conversion time
+    pub fn shunt_conversion_time(&mut self) -> Result<ConversionTime, Error> {
+        let time = (self.read_config_reg()? >> 3 & 0b111) as u8;
+        Ok(ConversionTime::from_primitive(time))
+    }
+
+    /// sets shunt voltage conversion time
+    pub fn set_shunt_conversion_time(&mut self, value: ConversionTime) -> Result<(), Error> {
+        let original_state = self.read_config_reg()?;
+        let new_value: u16 = value as u8 as u16;
+        let new_state = original_state & 0xFFC7 | new_value << 3;
+        self.write_config_reg(new_state)
+    }
+
+    /// gets bus voltage conversion time
+    pub fn bus_conversion_time(&mut self) -> Result<ConversionTime, Error> {
+        let time = (self.read_config_reg()? >> 6 & 0b111) as u8;
+        Ok(ConversionTime::from_primitive(time))
+    }
+
+    /// sets bus voltage conversion time
+    pub fn set_bus_conversion_time(&mut self, value: ConversionTime) -> Result<(), Error> {
+        let original_state = self.read_config_reg()?;
+        let new_value: u16 = value as u8 as u16;
+        let new_state = original_state & 0xFE3F | new_value << 6;
+        self.write_config_reg(new_state)
     }
 
     pub fn enable_all_channels(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
+        let original_state = self.read_config_reg()?;
         let new_value: u16 = 0b111;
         let new_state = original_state & 0x8fff | new_value << 12;
-        self.write_u16(0x00, new_state)
+        self.write_config_reg(new_state)
     }
 
     pub fn disable_all_channels(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
+        let original_state = self.read_config_reg()?;
         let new_state = original_state & 0x8fff;
-        self.write_u16(0x00, new_state)
+        self.write_config_reg(new_state)
+    }
+
+    /// checks whether a channel is enabled
+    pub fn channel_enabled(&mut self, channel: Channel) -> Result<bool, Error> {
+        Ok(self.read_config_reg()? >> channel.enable_bit() & 1 == 1)
+    }
+
+    /// enables or disables a channel
+    pub fn set_channel_enabled(&mut self, channel: Channel, enabled: bool) -> Result<(), Error> {
+        let original_state = self.read_config_reg()?;
+        let bit = channel.enable_bit();
+        let new_state = original_state & !(1 << bit) | (enabled as u16) << bit;
+        self.write_config_reg(new_state)
     }
 
     pub fn enable_channel1(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xbfff | 1 << 14;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch1, true)
     }
 
     pub fn disable_channel1(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xbfff;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch1, false)
     }
 
     pub fn enable_channel2(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xdfff | 1 << 13;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch2, true)
     }
 
     pub fn disable_channel2(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xdfff;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch2, false)
     }
 
     pub fn enable_channel3(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xefff | 1 << 12;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch3, true)
     }
 
     pub fn disable_channel3(&mut self) -> Result<(), Error> {
-        let original_state = self.read_u16(0x00)?;
-        let new_state = original_state & 0xefff;
-        self.write_u16(0x00, new_state)
+        self.set_channel_enabled(Channel::Ch3, false)
     }
 
     #[inline]
-    fn read_shunt_volt(&mut self, reg: u8) -> Result<i32, Error> {
-        let raw_value = self.read_u16(reg)?;
-        let signed_actual = (raw_value as i16) >> 3;
-        Ok(signed_actual as i32 * 40)
+    pub(crate) fn read_shunt_volt(&mut self, reg: Register) -> Result<i32, Error> {
+        Ok(decode_shunt(self.read_u16(reg)?))
     }
 
     #[inline]
-    fn read_bus_volt(&mut self, reg: u8) -> Result<i32, Error> {
-        let raw_value = self.read_u16(reg)?;
-        let signed_actual = (raw_value as i16) >> 3;
-        Ok(signed_actual as i32 * 8)
+    pub(crate) fn read_bus_volt(&mut self, reg: Register) -> Result<i32, Error> {
+        Ok(decode_bus(self.read_u16(reg)?))
+    }
+
+    /// Shunt voltage of a channel, in microvolt(uV).
+    pub fn shunt_voltage(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.read_shunt_volt(channel.shunt_register())
+    }
+
+    /// Bus voltage of a channel, in milivolt(mV).
+    pub fn bus_voltage(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.read_bus_volt(channel.bus_register())
+    }
+
+    /// Calculates current of a channel based on the resistor value provided.
+    ///
+    /// in milli-Amp
+    pub fn current(&mut self, channel: Channel) -> Result<i32, Error> {
+        let voltage = self.shunt_voltage(channel)?;
+        Ok(calc_current(voltage, self.shunt_resistor(channel)))
+    }
+
+    /// Calculates load power of a channel based on the resistor value provided.
+    ///
+    /// in milli-Watt
+    pub fn power(&mut self, channel: Channel) -> Result<i32, Error> {
+        let shunt_voltage = self.shunt_voltage(channel)?;
+        let bus_voltage = self.bus_voltage(channel)?;
+        Ok(calc_power(bus_voltage, shunt_voltage, self.shunt_resistor(channel)))
     }
 
     /// Shunt voltage channel 1, in microvolt(uV).
     pub fn shunt_channel1(&mut self) -> Result<i32, Error> {
-        self.read_shunt_volt(0x01)
+        self.shunt_voltage(Channel::Ch1)
     }
 
     /// Shunt voltage channel 1, in milivolt(mV).
     pub fn bus_channel1(&mut self) -> Result<i32, Error> {
-        self.read_bus_volt(0x02)
+        self.bus_voltage(Channel::Ch1)
     }
 
     /// Shunt voltage channel 2, in microvolt(uV).
     pub fn shunt_channel2(&mut self) -> Result<i32, Error> {
-        self.read_shunt_volt(0x03)
+        self.shunt_voltage(Channel::Ch2)
     }
 
     /// Shunt voltage channel 2, in milivolt(mV).
     pub fn bus_channel2(&mut self) -> Result<i32, Error> {
-        self.read_bus_volt(0x04)
+        self.bus_voltage(Channel::Ch2)
     }
     /// Shunt voltage channel 3, in microvolt(uV).
     pub fn shunt_channel3(&mut self) -> Result<i32, Error> {
-        self.read_shunt_volt(0x05)
+        self.shunt_voltage(Channel::Ch3)
     }
 
     /// Shunt voltage channel 3, in milivolt(mV).
     pub fn bus_channel3(&mut self) -> Result<i32, Error> {
-        self.read_bus_volt(0x06)
+        self.bus_voltage(Channel::Ch3)
     }
 
     /// Calculates current at channel 1 based on the resistor value provided.
     /// 
     /// in milli-Amp
     pub fn current_channel1(&mut self) -> Result<i32, Error> {
-        let voltage = self.shunt_channel1()?;
-        let resistor = self.shunt_r1 as i32;
-        Ok(voltage / resistor)
+        self.current(Channel::Ch1)
     }
 
     /// Calculates current at channel 2 based on the resistor value provided.
     /// 
     /// in milli-Amp
     pub fn current_channel2(&mut self) -> Result<i32, Error> {
-        let voltage = self.shunt_channel2()?;
-        let resistor = self.shunt_r2 as i32;
-        Ok(voltage / resistor)
+        self.current(Channel::Ch2)
     }
 
     /// Calculates current at channel 3 based on the resistor value provided.
     /// 
     /// in milli-Amp
     pub fn current_channel3(&mut self) -> Result<i32, Error> {
-        let voltage = self.shunt_channel3()?;
-        let resistor = self.shunt_r3 as i32;
-        Ok(voltage / resistor)
+        self.current(Channel::Ch3)
+    }
+
+    /// Calculates load power at channel 1 based on the resistor value provided.
+    ///
+    /// in milli-Watt
+    pub fn power_channel1(&mut self) -> Result<i32, Error> {
+        self.power(Channel::Ch1)
+    }
+
+    /// Calculates load power at channel 2 based on the resistor value provided.
+    ///
+    /// in milli-Watt
+    pub fn power_channel2(&mut self) -> Result<i32, Error> {
+        self.power(Channel::Ch2)
+    }
+
+    /// Calculates load power at channel 3 based on the resistor value provided.
+    ///
+    /// in milli-Watt
+    pub fn power_channel3(&mut self) -> Result<i32, Error> {
+        self.power(Channel::Ch3)
     }
 }
@@ -2,12 +2,109 @@
 #![warn(unsafe_code)]
 #![no_std]
 
+mod alert;
+mod alert_pin;
+mod config;
+mod energy;
+#[cfg(feature = "float")]
+mod float;
 mod general;
+mod mask;
+mod measurement;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+mod multi;
+mod oneshot;
+#[cfg(feature = "uom")]
+mod quantity;
+mod register;
+#[cfg(feature = "embedded-io")]
+mod report;
+#[cfg(test)]
+mod tests;
+mod typestate;
 
+use core::num::NonZeroU32;
+
+use embedded_hal::digital::ErrorKind as PinErrorKind;
 use embedded_hal::i2c::{Error as I2cError, ErrorKind as I2cErrorKind, I2c};
 
+pub use config::{conversion_time_us, Config};
+pub use energy::EnergyMeter;
+pub use general::{AveragingMode, Channel, ConversionTime, OperatingMode};
+pub use mask::MaskEnable;
+pub use measurement::{ChannelMeasurement, Measurements};
+pub use multi::synchronized_oneshot;
+pub use register::{Register, RegisterDump};
+pub use typestate::{Continuous, PowerDown, Triggered, TypedIna3221};
+
 pub const INA3221_DEFAULT_ADDR: u8 = 0x40;
 
+/// Where the A0 pin is strapped to, selecting the I2C address.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum A0Pin {
+    Gnd = 0x40,
+    Vs = 0x41,
+    Sda = 0x42,
+    Scl = 0x43,
+}
+
+impl A0Pin {
+    /// 7-bit I2C address selected by the strapping.
+    pub const fn address(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Shunt resistor value in micro-ohm, never zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MicroOhms(NonZeroU32);
+
+impl MicroOhms {
+    /// Returns `None` if `value` is zero.
+    pub const fn new(value: u32) -> Option<Self> {
+        match NonZeroU32::new(value) {
+            Some(value) => Some(Self(value)),
+            None => None,
+        }
+    }
+
+    /// Returns `None` if `value` is zero or too large.
+    pub const fn from_milli_ohms(value: u32) -> Option<Self> {
+        match value.checked_mul(1000) {
+            Some(value) => Self::new(value),
+            None => None,
+        }
+    }
+
+    pub const fn get(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+impl From<NonZeroU32> for MicroOhms {
+    fn from(value: NonZeroU32) -> Self {
+        Self(value)
+    }
+}
+
+/// 10 milli-ohm, the default shunt resistor value.
+const DEFAULT_SHUNT: MicroOhms = match MicroOhms::new(10_000) {
+    Some(value) => value,
+    None => unreachable!(),
+};
+
+/// Manufacturer ID of Texas Instruments.
+pub const INA3221_MANUFACTURER_ID: u16 = 0x5449;
+/// Die ID of INA3221.
+pub const INA3221_DIE_ID: u16 = 0x3220;
+
+/// Power-on value of the configuration register.
+const CONFIG_DEFAULT: u16 = 0x7127;
+
 /// INA3221 error type.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -15,6 +112,19 @@ pub const INA3221_DEFAULT_ADDR: u8 = 0x40;
 pub enum Error {
     /// An I2C error occurred during the transaction.
     I2cError(I2cErrorKind),
+    /// An error occurred while reading an alert pin.
+    PinError(PinErrorKind),
+    /// An IO error occurred while writing a report.
+    #[cfg(feature = "embedded-io")]
+    IoError(embedded_io::ErrorKind),
+    /// The address is not one of the 4 addresses an INA3221 can have(0x40-0x43).
+    InvalidAddress,
+    /// No device acknowledged the address.
+    NotFound,
+    /// The device at the address is not an INA3221.
+    WrongDevice { manufacturer_id: u16, die_id: u16 },
+    /// The conversion did not complete in time.
+    Timeout,
     /// Other error. The original error converted from may contain more information.
     Other,
 }
@@ -33,14 +143,16 @@ impl embedded_hal::digital::Error for Error {
 
 /// INA3221 struct.
 ///
-/// Shunt resistor values' unit is milli-ohm.
+/// Shunt resistor values are given as [`MicroOhms`], 10 milli-ohm by default.
 #[derive(Debug)]
 pub struct Ina3221<I2C> {
     i2c: I2C,
     address: u8,
-    shunt_r1: u8,
-    shunt_r2: u8,
-    shunt_r3: u8,
+    shunt_r1: MicroOhms,
+    shunt_r2: MicroOhms,
+    shunt_r3: MicroOhms,
+    use_config_cache: bool,
+    config_cache: Option<u16>,
 }
 
 impl<I2C: I2c> Ina3221<I2C> {
@@ -49,70 +161,197 @@ impl<I2C: I2c> Ina3221<I2C> {
         Self {
             i2c,
             address: INA3221_DEFAULT_ADDR,
-            shunt_r1: 10,
-            shunt_r2: 10,
-            shunt_r3: 10,
+            shunt_r1: DEFAULT_SHUNT,
+            shunt_r2: DEFAULT_SHUNT,
+            shunt_r3: DEFAULT_SHUNT,
+            use_config_cache: false,
+            config_cache: None,
         }
     }
 
+    /// Creates a driver for a chip at `address`.
+    ///
+    /// The address must be one of 0x40-0x43, in debug builds this is asserted.
+    /// Use [`Ina3221::try_new_with_addr`] to check it at runtime.
     #[must_use]
     pub fn new_with_addr(i2c: I2C, address: u8) -> Self {
+        debug_assert!(
+            (0x40..=0x43).contains(&address),
+            "invalid INA3221 address, expecting 7-bit address 0x40-0x43"
+        );
         Self {
             i2c,
             address,
-            shunt_r1: 10,
-            shunt_r2: 10,
-            shunt_r3: 10,
+            shunt_r1: DEFAULT_SHUNT,
+            shunt_r2: DEFAULT_SHUNT,
+            shunt_r3: DEFAULT_SHUNT,
+            use_config_cache: false,
+            config_cache: None,
+        }
+    }
+
+    /// Creates a driver for a chip at `address`, rejecting addresses outside 0x40-0x43.
+    pub fn try_new_with_addr(i2c: I2C, address: u8) -> Result<Self, Error> {
+        if !(0x40..=0x43).contains(&address) {
+            return Err(Error::InvalidAddress);
         }
+        Ok(Self::new_with_addr(i2c, address))
+    }
+
+    /// Creates a driver for a chip with its A0 pin strapped as `a0`.
+    #[must_use]
+    pub fn new_with_a0(i2c: I2C, a0: A0Pin) -> Self {
+        Self::new_with_addr(i2c, a0.address())
     }
 
+    /// Sets shunt resistor value of channel 1.
     #[must_use]
-    pub fn shunt_r1(mut self, value: u8) -> Self {
+    pub fn shunt_r1(mut self, value: MicroOhms) -> Self {
         self.shunt_r1 = value;
         self
     }
 
+    /// Sets shunt resistor value of channel 2.
     #[must_use]
-    pub fn shunt_r2(mut self, value: u8) -> Self {
+    pub fn shunt_r2(mut self, value: MicroOhms) -> Self {
         self.shunt_r2 = value;
         self
     }
 
+    /// Sets shunt resistor value of channel 3.
     #[must_use]
-    pub fn shunt_r3(mut self, value: u8) -> Self {
+    pub fn shunt_r3(mut self, value: MicroOhms) -> Self {
         self.shunt_r3 = value;
         self
     }
 
+    /// Keeps a copy of the configuration register in memory.
+    ///
+    /// Configuration changes then only write to the chip instead of doing a
+    /// read-modify-write. Use [`Ina3221::sync_config`] or [`Ina3221::invalidate_cache`]
+    /// if the chip may have been reset or reconfigured by someone else.
+    #[must_use]
+    pub fn with_config_cache(mut self) -> Self {
+        self.use_config_cache = true;
+        self
+    }
+
+    /// Reloads the cached configuration register from the chip.
+    pub fn sync_config(&mut self) -> Result<(), Error> {
+        self.config_cache = None;
+        self.read_config_reg().map(|_| ())
+    }
+
+    /// Drops the cached configuration register, it will be read again on next access.
+    pub fn invalidate_cache(&mut self) {
+        self.config_cache = None;
+    }
+
+    #[inline]
+    fn shunt_resistor(&self, channel: Channel) -> u32 {
+        match channel {
+            Channel::Ch1 => self.shunt_r1.get(),
+            Channel::Ch2 => self.shunt_r2.get(),
+            Channel::Ch3 => self.shunt_r3.get(),
+        }
+    }
+
     pub fn destroy(self) -> I2C {
         self.i2c
     }
 
+    /// Checks that an INA3221 answers at the configured address.
+    ///
+    /// Returns [`Error::NotFound`] if nothing acknowledges the address, and
+    /// [`Error::WrongDevice`] if the manufacturer ID or die ID doesn't match.
+    pub fn probe(&mut self) -> Result<(), Error> {
+        let manufacturer_id = self.manufacturer_id().map_err(|e| match e {
+            Error::I2cError(I2cErrorKind::NoAcknowledge(_)) => Error::NotFound,
+            e => e,
+        })?;
+        let die_id = self.die_id()?;
+        if manufacturer_id != INA3221_MANUFACTURER_ID || die_id != INA3221_DIE_ID {
+            return Err(Error::WrongDevice {
+                manufacturer_id,
+                die_id,
+            });
+        }
+        Ok(())
+    }
+
     pub fn manufacturer_id(&mut self) -> Result<u16, Error> {
-        self.read_u16(0xFE)
+        self.read_u16(Register::ManufacturerId)
     }
 
     pub fn die_id(&mut self) -> Result<u16, Error> {
-        self.read_u16(0xFF)
+        self.read_u16(Register::DieId)
     }
 
-    fn read_u16(&mut self, reg: u8) -> Result<u16, Error> {
+    /// Reads a register directly.
+    ///
+    /// An escape hatch for what the high-level API doesn't cover. Mind that
+    /// reading [`Register::MaskEnable`] clears the flags.
+    pub fn read_register(&mut self, register: Register) -> Result<u16, Error> {
+        let value = self.read_u16(register)?;
+        if register == Register::Configuration && self.use_config_cache {
+            self.config_cache = Some(value);
+        }
+        Ok(value)
+    }
+
+    /// Writes a register directly.
+    ///
+    /// An escape hatch for what the high-level API doesn't cover. Writes to
+    /// read-only registers are ignored by the chip.
+    pub fn write_register(&mut self, register: Register, value: u16) -> Result<(), Error> {
+        match register {
+            Register::Configuration => self.write_config_reg(value),
+            _ => self.write_u16(register, value),
+        }
+    }
+
+    fn read_u16(&mut self, reg: Register) -> Result<u16, Error> {
         let mut buf: [u8; 2] = [0; 2];
         self.read_buf(reg, &mut buf)?;
         let value: u16 = ((buf[0] as u16) << 8) | (buf[1] as u16);
         Ok(value)
     }
 
-    fn write_u16(&mut self, reg: u8, value: u16) -> Result<(), Error> {
+    fn write_u16(&mut self, reg: Register, value: u16) -> Result<(), Error> {
         let mut buf: [u8; 3] = [0; 3];
-        buf[0] = reg;
+        buf[0] = reg.address();
         buf[1] = (value >> 8) as u8;
-        buf[2] = (value & 0xF) as u8;
+        buf[2] = (value & 0xFF) as u8;
         Ok(self.i2c.write(self.address, &buf)?)
     }
 
+    fn read_config_reg(&mut self) -> Result<u16, Error> {
+        if let Some(value) = self.config_cache {
+            return Ok(value);
+        }
+        let value = self.read_u16(Register::Configuration)?;
+        if self.use_config_cache {
+            self.config_cache = Some(value);
+        }
+        Ok(value)
+    }
+
+    fn write_config_reg(&mut self, value: u16) -> Result<(), Error> {
+        self.write_u16(Register::Configuration, value)?;
+        if self.use_config_cache {
+            // the reset bit restores the power-on configuration and self-clears
+            let value = if value & 0x8000 != 0 {
+                CONFIG_DEFAULT
+            } else {
+                value
+            };
+            self.config_cache = Some(value);
+        }
+        Ok(())
+    }
+
     #[inline]
-    fn read_buf(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Error> {
-        Ok(self.i2c.write_read(self.address, &[reg], buf)?)
+    fn read_buf(&mut self, reg: Register, buf: &mut [u8]) -> Result<(), Error> {
+        Ok(self.i2c.write_read(self.address, &[reg.address()], buf)?)
     }
 }
@@ -0,0 +1,201 @@
+use core::fmt;
+
+use crate::{Channel, Error, I2c, Ina3221, Register};
+
+/// Writable bits of the Mask/Enable register: SCC1-3, WEN and CEN.
+pub(crate) const WRITABLE_BITS: u16 = 0x7C00;
+
+/// Content of the Mask/Enable register(0x0F).
+///
+/// Holds the summation control, the alert latch settings and the status flags.
+/// The flags are read-only, and are ignored when written back to the chip.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MaskEnable(u16);
+
+impl MaskEnable {
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    #[inline]
+    const fn bit(&self, bit: u8) -> bool {
+        self.0 >> bit & 1 == 1
+    }
+
+    #[inline]
+    fn set_bit(&mut self, bit: u8, value: bool) {
+        self.0 = self.0 & !(1 << bit) | (value as u16) << bit;
+    }
+
+    /// Whether the shunt voltage of a channel is included in the summation.
+    pub const fn summation(&self, channel: Channel) -> bool {
+        self.bit(14 - channel.index() as u8)
+    }
+
+    pub fn set_summation(&mut self, channel: Channel, value: bool) {
+        self.set_bit(14 - channel.index() as u8, value)
+    }
+
+    /// Critical alert flag of a channel.
+    pub const fn critical(&self, channel: Channel) -> bool {
+        self.bit(9 - channel.index() as u8)
+    }
+
+    /// Warning alert flag of a channel.
+    pub const fn warning(&self, channel: Channel) -> bool {
+        self.bit(5 - channel.index() as u8)
+    }
+
+    /// Whether the warning alert pin is latched until the register is read.
+    pub const fn warning_latch(&self) -> bool {
+        self.bit(11)
+    }
+
+    pub fn set_warning_latch(&mut self, value: bool) {
+        self.set_bit(11, value)
+    }
+
+    /// Whether the critical alert pin is latched until the register is read.
+    pub const fn critical_latch(&self) -> bool {
+        self.bit(10)
+    }
+
+    pub fn set_critical_latch(&mut self, value: bool) {
+        self.set_bit(10, value)
+    }
+
+    /// Summation alert flag.
+    pub const fn summation_alert(&self) -> bool {
+        self.bit(6)
+    }
+
+    /// Power-valid alert flag. Set when all bus voltages are above the power-valid upper limit.
+    pub const fn power_valid(&self) -> bool {
+        self.bit(2)
+    }
+
+    /// Timing control alert flag.
+    pub const fn timing_control(&self) -> bool {
+        self.bit(1)
+    }
+
+    /// Conversion ready flag.
+    pub const fn conversion_ready(&self) -> bool {
+        self.bit(0)
+    }
+}
+
+/// Writes the numbers of the channels set in `channels`, e.g. "1+3", or "none".
+pub(crate) fn fmt_channels(f: &mut fmt::Formatter<'_>, channels: [bool; 3]) -> fmt::Result {
+    if !channels.contains(&true) {
+        return f.write_str("none");
+    }
+    let mut first = true;
+    for (index, _) in channels.iter().enumerate().filter(|(_, set)| **set) {
+        let separator = if first { "" } else { "+" };
+        write!(f, "{}ch{}", separator, index + 1)?;
+        first = false;
+    }
+    Ok(())
+}
+
+impl fmt::Display for MaskEnable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("summation ")?;
+        fmt_channels(f, Channel::ALL.map(|channel| self.summation(channel)))?;
+        write!(
+            f,
+            ", latch warning={} critical={}, critical ",
+            self.warning_latch(),
+            self.critical_latch(),
+        )?;
+        fmt_channels(f, Channel::ALL.map(|channel| self.critical(channel)))?;
+        f.write_str(", warning ")?;
+        fmt_channels(f, Channel::ALL.map(|channel| self.warning(channel)))?;
+        write!(
+            f,
+            ", sum alert={}, power valid={}, timing={}, ready={}",
+            self.summation_alert(),
+            self.power_valid(),
+            self.timing_control(),
+            self.conversion_ready(),
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for MaskEnable {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "summation={=bool}/{=bool}/{=bool}, latch warning={=bool} critical={=bool}, \
+            critical={=bool}/{=bool}/{=bool}, warning={=bool}/{=bool}/{=bool}, \
+            sum alert={=bool}, power valid={=bool}, timing={=bool}, ready={=bool}",
+            self.summation(Channel::Ch1),
+            self.summation(Channel::Ch2),
+            self.summation(Channel::Ch3),
+            self.warning_latch(),
+            self.critical_latch(),
+            self.critical(Channel::Ch1),
+            self.critical(Channel::Ch2),
+            self.critical(Channel::Ch3),
+            self.warning(Channel::Ch1),
+            self.warning(Channel::Ch2),
+            self.warning(Channel::Ch3),
+            self.summation_alert(),
+            self.power_valid(),
+            self.timing_control(),
+            self.conversion_ready(),
+        )
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Reads the Mask/Enable register.
+    ///
+    /// Reading this register clears the conversion ready flag, and the latched
+    /// warning and critical alert flags.
+    pub fn flags(&mut self) -> Result<MaskEnable, Error> {
+        Ok(MaskEnable(self.read_u16(Register::MaskEnable)?))
+    }
+
+    /// Writes the summation control and alert latch settings.
+    ///
+    /// Flags are read-only and are not written.
+    pub fn set_mask_enable(&mut self, value: MaskEnable) -> Result<(), Error> {
+        self.write_u16(Register::MaskEnable, value.0 & WRITABLE_BITS)
+    }
+
+    /// Selects which channels are included in the shunt voltage summation, indexed by [`Channel::index`].
+    ///
+    /// This reads the Mask/Enable register, so flags will be cleared.
+    pub fn set_summation_channels(&mut self, channels: [bool; 3]) -> Result<(), Error> {
+        let mut value = self.flags()?;
+        for channel in Channel::ALL {
+            value.set_summation(channel, channels[channel.index()]);
+        }
+        self.set_mask_enable(value)
+    }
+
+    /// Sets whether the warning alert pin is latched.
+    ///
+    /// This reads the Mask/Enable register, so flags will be cleared.
+    pub fn set_warning_latch(&mut self, value: bool) -> Result<(), Error> {
+        let mut state = self.flags()?;
+        state.set_warning_latch(value);
+        self.set_mask_enable(state)
+    }
+
+    /// Sets whether the critical alert pin is latched.
+    ///
+    /// This reads the Mask/Enable register, so flags will be cleared.
+    pub fn set_critical_latch(&mut self, value: bool) -> Result<(), Error> {
+        let mut state = self.flags()?;
+        state.set_critical_latch(value);
+        self.set_mask_enable(state)
+    }
+}
@@ -0,0 +1,151 @@
+use core::ops::Index;
+
+use embedded_hal::i2c::Operation;
+
+use crate::{Channel, Error, I2c, Ina3221, Register};
+
+/// Decodes a shunt voltage register value to microvolt(uV).
+#[inline]
+pub(crate) fn decode_shunt(raw_value: u16) -> i32 {
+    ((raw_value as i16) >> 3) as i32 * 40
+}
+
+/// Decodes a bus voltage register value to milivolt(mV).
+#[inline]
+pub(crate) fn decode_bus(raw_value: u16) -> i32 {
+    ((raw_value as i16) >> 3) as i32 * 8
+}
+
+/// Encodes microvolt(uV) to a shunt voltage register value.
+///
+/// The value is rounded towards zero to a multiple of 40uV and clamped to the register range.
+#[inline]
+pub(crate) fn encode_shunt(value: i32) -> u16 {
+    let steps = (value / 40).clamp(-4096, 4095) as i16;
+    (steps << 3) as u16
+}
+
+/// Encodes milivolt(mV) to a bus voltage register value.
+///
+/// The value is rounded towards zero to a multiple of 8mV and clamped to the register range.
+#[inline]
+pub(crate) fn encode_bus(value: i32) -> u16 {
+    let steps = (value / 8).clamp(-4096, 4095) as i16;
+    (steps << 3) as u16
+}
+
+/// Decodes a shunt voltage sum register value to microvolt(uV).
+#[inline]
+pub(crate) fn decode_sum(raw_value: u16) -> i32 {
+    ((raw_value as i16) >> 1) as i32 * 40
+}
+
+/// Encodes microvolt(uV) to a shunt voltage sum register value.
+///
+/// The value is rounded towards zero to a multiple of 40uV and clamped to the register range,
+/// which has 2 more bits than a single channel.
+#[inline]
+pub(crate) fn encode_sum(value: i32) -> u16 {
+    let steps = (value / 40).clamp(-16384, 16383) as i16;
+    (steps << 1) as u16
+}
+
+/// Calculates current in milli-Amp(mA) from shunt voltage(uV) and shunt resistor(micro-ohm).
+#[inline]
+pub(crate) fn calc_current(shunt_voltage: i32, resistor: u32) -> i32 {
+    (shunt_voltage as i64 * 1000 / resistor as i64) as i32
+}
+
+/// Calculates power in milli-Watt(mW) from bus voltage(mV), shunt voltage(uV) and shunt resistor(micro-ohm).
+///
+/// Calculated from the shunt voltage rather than the rounded current to keep the precision.
+#[inline]
+pub(crate) fn calc_power(bus_voltage: i32, shunt_voltage: i32, resistor: u32) -> i32 {
+    let power = bus_voltage as i64 * shunt_voltage as i64 / resistor as i64;
+    power.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// Measurement of a single channel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChannelMeasurement {
+    /// Shunt voltage, in microvolt(uV).
+    pub shunt_voltage: i32,
+    /// Bus voltage, in milivolt(mV).
+    pub bus_voltage: i32,
+    /// Current, in milli-Amp(mA).
+    pub current: i32,
+    /// Power, in milli-Watt(mW).
+    pub power: i32,
+}
+
+impl ChannelMeasurement {
+    fn new(shunt_voltage: i32, bus_voltage: i32, resistor: u32) -> Self {
+        let current = calc_current(shunt_voltage, resistor);
+        Self {
+            shunt_voltage,
+            bus_voltage,
+            current,
+            power: calc_power(bus_voltage, shunt_voltage, resistor),
+        }
+    }
+}
+
+/// Measurements of all 3 channels, indexable by [`Channel`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Measurements {
+    pub channels: [ChannelMeasurement; 3],
+}
+
+impl Measurements {
+    pub fn channel(&self, channel: Channel) -> &ChannelMeasurement {
+        &self.channels[channel.index()]
+    }
+}
+
+impl Index<Channel> for Measurements {
+    type Output = ChannelMeasurement;
+
+    fn index(&self, channel: Channel) -> &Self::Output {
+        self.channel(channel)
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Reads shunt and bus voltages of all 3 channels in a single I2C transaction.
+    ///
+    /// The chip does not auto-increment its register pointer, so each register
+    /// is addressed with a repeated start. The bus is not released in between,
+    /// so the values are read as close together as possible.
+    pub fn read_all(&mut self) -> Result<Measurements, Error> {
+        let mut buf = [[0u8; 2]; 6];
+        let [b1, b2, b3, b4, b5, b6] = &mut buf;
+        let mut operations = [
+            Operation::Write(&[Register::ShuntVoltage1.address()]),
+            Operation::Read(b1),
+            Operation::Write(&[Register::BusVoltage1.address()]),
+            Operation::Read(b2),
+            Operation::Write(&[Register::ShuntVoltage2.address()]),
+            Operation::Read(b3),
+            Operation::Write(&[Register::BusVoltage2.address()]),
+            Operation::Read(b4),
+            Operation::Write(&[Register::ShuntVoltage3.address()]),
+            Operation::Read(b5),
+            Operation::Write(&[Register::BusVoltage3.address()]),
+            Operation::Read(b6),
+        ];
+        self.i2c.transaction(self.address, &mut operations)?;
+
+        let mut measurements = Measurements::default();
+        for channel in Channel::ALL {
+            let shunt = buf[2 * channel.index()];
+            let bus = buf[2 * channel.index() + 1];
+            let shunt_voltage = decode_shunt(u16::from_be_bytes(shunt));
+            let bus_voltage = decode_bus(u16::from_be_bytes(bus));
+            measurements.channels[channel.index()] =
+                ChannelMeasurement::new(shunt_voltage, bus_voltage, self.shunt_resistor(channel));
+        }
+        Ok(measurements)
+    }
+}
@@ -0,0 +1,244 @@
+//! In-memory model of an INA3221 for host-side testing.
+//!
+//! [`Ina3221Mock`] implements [`embedded_hal::i2c::I2c`], so it can be handed
+//! to [`crate::Ina3221`] in place of a real bus. Measurements are set from the
+//! test with [`Ina3221Mock::set_shunt_voltage`] and [`Ina3221Mock::set_bus_voltage`],
+//! and the alert flags, shunt voltage sum and conversion ready flag follow them.
+//!
+//! The model handles the register pointer, the reset bit, read-only
+//! registers and clearing of the flags on reading the Mask/Enable register.
+//! Conversions complete instantly.
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::mask::WRITABLE_BITS;
+use crate::measurement::{decode_shunt, decode_sum, encode_bus, encode_shunt, encode_sum};
+use crate::{Channel, Register, CONFIG_DEFAULT, INA3221_DIE_ID, INA3221_MANUFACTURER_ID};
+
+/// Number of registers from 0x00 to 0x11.
+const REGISTER_COUNT: usize = 0x12;
+
+const RESET_VALUES: [u16; REGISTER_COUNT] = [
+    CONFIG_DEFAULT,
+    0,
+    0,
+    0,
+    0,
+    0,
+    0,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0x7FF8,
+    0,
+    0x7FFE,
+    0x0002,
+    0x2710,
+    0x2328,
+];
+
+/// Simulated INA3221.
+#[derive(Debug, Clone)]
+pub struct Ina3221Mock {
+    address: u8,
+    pointer: u8,
+    registers: [u16; REGISTER_COUNT],
+    manufacturer_id: u16,
+    die_id: u16,
+}
+
+impl Default for Ina3221Mock {
+    fn default() -> Self {
+        Self::new(crate::INA3221_DEFAULT_ADDR)
+    }
+}
+
+impl Ina3221Mock {
+    /// Creates a simulated chip answering at `address`, in power-on state.
+    pub fn new(address: u8) -> Self {
+        Self {
+            address,
+            pointer: 0,
+            registers: RESET_VALUES,
+            manufacturer_id: INA3221_MANUFACTURER_ID,
+            die_id: INA3221_DIE_ID,
+        }
+    }
+
+    /// Restores the power-on state, like setting the reset bit.
+    pub fn reset(&mut self) {
+        self.registers = RESET_VALUES;
+    }
+
+    /// Reads a register without any side effect.
+    pub fn register(&self, register: Register) -> u16 {
+        match register {
+            Register::ManufacturerId => self.manufacturer_id,
+            Register::DieId => self.die_id,
+            _ => self.registers[register.address() as usize],
+        }
+    }
+
+    /// Overwrites a register, including read-only ones, without any side effect.
+    ///
+    /// Setting the ID registers simulates a different device.
+    pub fn set_register(&mut self, register: Register, value: u16) {
+        match register {
+            Register::ManufacturerId => self.manufacturer_id = value,
+            Register::DieId => self.die_id = value,
+            _ => self.registers[register.address() as usize] = value,
+        }
+    }
+
+    /// Sets the shunt voltage of a channel, in microvolt(uV).
+    pub fn set_shunt_voltage(&mut self, channel: Channel, value: i32) {
+        self.set_register(channel.shunt_register(), encode_shunt(value));
+        self.update_flags();
+    }
+
+    /// Sets the bus voltage of a channel, in milivolt(mV).
+    pub fn set_bus_voltage(&mut self, channel: Channel, value: i32) {
+        self.set_register(channel.bus_register(), encode_bus(value));
+        self.update_flags();
+    }
+
+    fn mask_enable(&self) -> u16 {
+        self.register(Register::MaskEnable)
+    }
+
+    /// Completes a conversion: recomputes the sum and the alert flags and sets
+    /// the conversion ready flag. Does nothing when powered down.
+    fn update_flags(&mut self) {
+        let config = self.register(Register::Configuration);
+        if config & 0b111 == 0 || config & 0b111 == 4 {
+            return;
+        }
+        let mut flags = self.mask_enable();
+        let latch_warning = flags >> 11 & 1 == 1;
+        let latch_critical = flags >> 10 & 1 == 1;
+
+        let mut sum: i32 = 0;
+        for channel in Channel::ALL {
+            let index = channel.index() as u16;
+            let shunt = self.register(channel.shunt_register()) as i16 >> 3;
+            if flags >> (14 - index) & 1 == 1 {
+                sum += decode_shunt(self.register(channel.shunt_register()));
+            }
+
+            let critical = self.register(channel.critical_limit_register()) as i16 >> 3;
+            let critical_bit = 1 << (9 - index);
+            if shunt > critical {
+                flags |= critical_bit;
+            } else if !latch_critical {
+                flags &= !critical_bit;
+            }
+
+            let warning = self.register(channel.warning_limit_register()) as i16 >> 3;
+            let warning_bit = 1 << (5 - index);
+            if shunt > warning {
+                flags |= warning_bit;
+            } else if !latch_warning {
+                flags &= !warning_bit;
+            }
+        }
+
+        self.set_register(Register::ShuntVoltageSum, encode_sum(sum));
+        let sum = decode_sum(self.register(Register::ShuntVoltageSum));
+        let sum_limit = decode_sum(self.register(Register::ShuntVoltageSumLimit));
+        if sum > sum_limit {
+            flags |= 1 << 6;
+        } else {
+            flags &= !(1 << 6);
+        }
+
+        let upper = self.register(Register::PowerValidUpperLimit) as i16 >> 3;
+        let lower = self.register(Register::PowerValidLowerLimit) as i16 >> 3;
+        let buses = Channel::ALL.map(|channel| self.register(channel.bus_register()) as i16 >> 3);
+        if buses.iter().all(|bus| *bus > upper) {
+            flags |= 1 << 2;
+        } else if buses.iter().any(|bus| *bus < lower) {
+            flags &= !(1 << 2);
+        }
+
+        // conversion ready
+        flags |= 1;
+        self.set_register(Register::MaskEnable, flags);
+    }
+
+    fn read_pointed(&mut self) -> u16 {
+        let value = match self.pointer {
+            0xFE => self.manufacturer_id,
+            0xFF => self.die_id,
+            pointer => self.registers.get(pointer as usize).copied().unwrap_or(0),
+        };
+        if self.pointer == Register::MaskEnable.address() {
+            // conversion ready flag and latched alert flags are cleared on read
+            let mut cleared = 1;
+            if value >> 11 & 1 == 1 {
+                cleared |= 0b111 << 3;
+            }
+            if value >> 10 & 1 == 1 {
+                cleared |= 0b111 << 7;
+            }
+            self.set_register(Register::MaskEnable, value & !cleared);
+        }
+        value
+    }
+
+    fn write_pointed(&mut self, value: u16) {
+        match self.pointer {
+            0x00 if value & 0x8000 != 0 => self.reset(),
+            0x00 => {
+                self.registers[0x00] = value;
+                // writing the configuration starts a new conversion
+                self.registers[0x0F] &= !1;
+                self.update_flags();
+            }
+            0x07..=0x0C | 0x10 | 0x11 => self.registers[self.pointer as usize] = value & 0xFFF8,
+            0x0E => self.registers[0x0E] = value & 0xFFFE,
+            0x0F => {
+                let flags = self.registers[0x0F] & !WRITABLE_BITS;
+                self.registers[0x0F] = flags | value & WRITABLE_BITS;
+            }
+            // measurement, ID and unknown registers are read-only
+            _ => {}
+        }
+    }
+}
+
+impl ErrorType for Ina3221Mock {
+    type Error = ErrorKind;
+}
+
+impl I2c for Ina3221Mock {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    if let Some(pointer) = bytes.first() {
+                        self.pointer = *pointer;
+                    }
+                    if let [_, high, low, ..] = bytes {
+                        self.write_pointed(u16::from_be_bytes([*high, *low]));
+                    }
+                }
+                Operation::Read(buf) => {
+                    let value = self.read_pointed().to_be_bytes();
+                    for (byte, value) in buf.iter_mut().zip(value.iter().cycle()) {
+                        *byte = *value;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
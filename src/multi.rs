@@ -0,0 +1,33 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::general::OperatingMode;
+use crate::{Error, I2c, Ina3221, Measurements};
+
+/// Triggers one-shot shunt and bus conversions on all devices and gathers the results.
+///
+/// The configuration registers are read first, so the trigger writes can be
+/// issued back-to-back to minimize the skew between devices. Then every device
+/// is polled with [`Ina3221::wait_conversion_ready`] until its conversion is
+/// ready, and all channels are read with [`Ina3221::read_all`].
+///
+/// Averaging, conversion times and enabled channels are kept as configured.
+pub fn synchronized_oneshot<I2C: I2c, D: DelayNs, const N: usize>(
+    devices: &mut [Ina3221<I2C>; N],
+    delay: &mut D,
+) -> Result<[Measurements; N], Error> {
+    let mut configs = [0u16; N];
+    for (config, device) in configs.iter_mut().zip(devices.iter_mut()) {
+        *config = device.read_config_reg()? & 0xFFF8 | OperatingMode::OneshotShuntBus as u16;
+    }
+
+    for (config, device) in configs.iter().zip(devices.iter_mut()) {
+        device.write_config_reg(*config)?;
+    }
+
+    let mut results = [Measurements::default(); N];
+    for (measurements, device) in results.iter_mut().zip(devices.iter_mut()) {
+        device.wait_conversion_ready(delay)?;
+        *measurements = device.read_all()?;
+    }
+    Ok(results)
+}
@@ -0,0 +1,45 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::general::OperatingMode;
+use crate::{Error, I2c, Ina3221, Measurements};
+
+/// Interval between polls of the conversion ready flag, in microsecond(us).
+const POLL_INTERVAL_US: u32 = 500;
+
+/// Longest possible conversion: 1024 samples of 3 channels, 8.244ms each for shunt and bus.
+const MAX_CONVERSION_TIME_US: u32 = 1024 * 3 * 2 * 8244;
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Triggers a single shunt and bus conversion of all enabled channels.
+    ///
+    /// Averaging, conversion times and enabled channels are kept as configured.
+    pub fn trigger_oneshot(&mut self) -> Result<(), Error> {
+        self.set_power_mode(OperatingMode::OneshotShuntBus)
+    }
+
+    /// Polls the conversion ready flag until the conversion is completed.
+    ///
+    /// Returns [`Error::Timeout`] if the flag is not set within the longest possible conversion time.
+    /// This reads the Mask/Enable register, so flags will be cleared.
+    pub fn wait_conversion_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error> {
+        let mut elapsed = 0;
+        while !self.flags()?.conversion_ready() {
+            if elapsed > MAX_CONVERSION_TIME_US {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            elapsed += POLL_INTERVAL_US;
+        }
+        Ok(())
+    }
+
+    /// Triggers a single conversion, waits for it and reads all channels.
+    ///
+    /// Sleeps for the expected conversion time before polling the conversion ready flag.
+    pub fn measure_oneshot<D: DelayNs>(&mut self, delay: &mut D) -> Result<Measurements, Error> {
+        self.trigger_oneshot()?;
+        delay.delay_us(self.measurement_interval_us()?);
+        self.wait_conversion_ready(delay)?;
+        self.read_all()
+    }
+}
@@ -0,0 +1,34 @@
+use uom::si::electric_current::ampere;
+use uom::si::electric_potential::{microvolt, millivolt};
+use uom::si::f32::{ElectricCurrent, ElectricPotential, Power};
+
+use crate::{Channel, Error, I2c, Ina3221};
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Shunt voltage of a channel.
+    pub fn shunt_voltage_quantity(&mut self, channel: Channel) -> Result<ElectricPotential, Error> {
+        let value = self.shunt_voltage(channel)?;
+        Ok(ElectricPotential::new::<microvolt>(value as f32))
+    }
+
+    /// Bus voltage of a channel.
+    pub fn bus_voltage_quantity(&mut self, channel: Channel) -> Result<ElectricPotential, Error> {
+        let value = self.bus_voltage(channel)?;
+        Ok(ElectricPotential::new::<millivolt>(value as f32))
+    }
+
+    /// Calculates current of a channel based on the resistor value provided.
+    pub fn current_quantity(&mut self, channel: Channel) -> Result<ElectricCurrent, Error> {
+        let value = self.shunt_voltage(channel)?;
+        // uV / micro-ohm = A
+        let current = value as f32 / self.shunt_resistor(channel) as f32;
+        Ok(ElectricCurrent::new::<ampere>(current))
+    }
+
+    /// Calculates load power of a channel based on the resistor value provided.
+    pub fn power_quantity(&mut self, channel: Channel) -> Result<Power, Error> {
+        let current = self.current_quantity(channel)?;
+        let voltage = self.bus_voltage_quantity(channel)?;
+        Ok(voltage * current)
+    }
+}
@@ -0,0 +1,115 @@
+use core::fmt;
+
+use num_enum::IntoPrimitive;
+
+use crate::{Error, I2c, Ina3221};
+
+/// Registers of the INA3221.
+#[repr(u8)]
+#[derive(IntoPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Register {
+    Configuration = 0x00,
+    ShuntVoltage1 = 0x01,
+    BusVoltage1 = 0x02,
+    ShuntVoltage2 = 0x03,
+    BusVoltage2 = 0x04,
+    ShuntVoltage3 = 0x05,
+    BusVoltage3 = 0x06,
+    CriticalLimit1 = 0x07,
+    WarningLimit1 = 0x08,
+    CriticalLimit2 = 0x09,
+    WarningLimit2 = 0x0A,
+    CriticalLimit3 = 0x0B,
+    WarningLimit3 = 0x0C,
+    ShuntVoltageSum = 0x0D,
+    ShuntVoltageSumLimit = 0x0E,
+    MaskEnable = 0x0F,
+    PowerValidUpperLimit = 0x10,
+    PowerValidLowerLimit = 0x11,
+    ManufacturerId = 0xFE,
+    DieId = 0xFF,
+}
+
+impl Register {
+    /// All documented registers, in address order.
+    pub const ALL: [Register; 20] = [
+        Register::Configuration,
+        Register::ShuntVoltage1,
+        Register::BusVoltage1,
+        Register::ShuntVoltage2,
+        Register::BusVoltage2,
+        Register::ShuntVoltage3,
+        Register::BusVoltage3,
+        Register::CriticalLimit1,
+        Register::WarningLimit1,
+        Register::CriticalLimit2,
+        Register::WarningLimit2,
+        Register::CriticalLimit3,
+        Register::WarningLimit3,
+        Register::ShuntVoltageSum,
+        Register::ShuntVoltageSumLimit,
+        Register::MaskEnable,
+        Register::PowerValidUpperLimit,
+        Register::PowerValidLowerLimit,
+        Register::ManufacturerId,
+        Register::DieId,
+    ];
+
+    /// Register pointer address.
+    pub const fn address(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Raw values of all documented registers, see [`Ina3221::dump_registers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDump {
+    /// Values in the order of [`Register::ALL`].
+    pub values: [u16; Register::ALL.len()],
+}
+
+impl RegisterDump {
+    /// Value of a register.
+    pub fn get(&self, register: Register) -> u16 {
+        let index = Register::ALL
+            .iter()
+            .position(|r| *r == register)
+            .unwrap_or_default();
+        self.values[index]
+    }
+
+    /// Registers paired with their values, in address order.
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u16)> + '_ {
+        Register::ALL.into_iter().zip(self.values)
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (register, value) in self.iter() {
+            writeln!(
+                f,
+                "0x{:02x} {:?}: 0x{:04x}",
+                register.address(),
+                register,
+                value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Reads all documented registers for diagnostics.
+    ///
+    /// This reads the Mask/Enable register, so flags will be cleared.
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Error> {
+        let mut values = [0; Register::ALL.len()];
+        for (value, register) in values.iter_mut().zip(Register::ALL) {
+            *value = self.read_u16(register)?;
+        }
+        Ok(RegisterDump { values })
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{Channel, Error, I2c, Ina3221};
+
+use embedded_io::{Error as IoError, Write, WriteFmtError};
+
+fn io_error<E: IoError>(error: WriteFmtError<E>) -> Error {
+    match error {
+        WriteFmtError::Other(e) => Error::IoError(e.kind()),
+        WriteFmtError::FmtError => Error::Other,
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Writes a human-readable multi-line status report to `writer`.
+    ///
+    /// The report contains the configuration, readings of all 3 channels and
+    /// the alert flags. Note that reading the alert flags clears them.
+    pub fn write_report<W: Write>(&mut self, writer: &mut W) -> Result<(), Error> {
+        let config = self.read_config()?;
+        writeln!(writer, "INA3221 @ 0x{:02x}", self.address).map_err(io_error)?;
+        writeln!(writer, "config: 0x{:04x} {}", config.bits(), config).map_err(io_error)?;
+
+        let measurements = self.read_all()?;
+        for channel in Channel::ALL {
+            let measurement = measurements[channel];
+            writeln!(
+                writer,
+                "{}: shunt={}uV bus={}mV current={}mA power={}mW",
+                channel,
+                measurement.shunt_voltage,
+                measurement.bus_voltage,
+                measurement.current,
+                measurement.power,
+            )
+            .map_err(io_error)?;
+        }
+
+        let flags = self.flags()?;
+        writeln!(writer, "alerts: {}", flags).map_err(io_error)?;
+        Ok(())
+    }
+}
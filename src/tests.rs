@@ -0,0 +1,504 @@
+extern crate std;
+
+use embedded_hal::delay::DelayNs;
+
+use crate::mock::Ina3221Mock;
+use crate::{
+    A0Pin, AveragingMode, Channel, Config, ConversionTime, Error, Ina3221, MaskEnable,
+    Measurements, MicroOhms, OperatingMode, Register, CONFIG_DEFAULT,
+};
+
+/// Delay that returns immediately, the mock converts instantly.
+struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+fn device() -> Ina3221<Ina3221Mock> {
+    Ina3221::new(Ina3221Mock::default())
+}
+
+#[test]
+fn shunt_limits_round_trip() {
+    let mut ina = device();
+    for channel in Channel::ALL {
+        ina.set_critical_limit(channel, 40_000).unwrap();
+        assert_eq!(ina.critical_limit(channel).unwrap(), 40_000);
+        ina.set_warning_limit(channel, -1_200).unwrap();
+        assert_eq!(ina.warning_limit(channel).unwrap(), -1_200);
+    }
+}
+
+#[test]
+fn shunt_limits_round_towards_zero() {
+    let mut ina = device();
+    ina.set_critical_limit(Channel::Ch1, 79).unwrap();
+    assert_eq!(ina.critical_limit(Channel::Ch1).unwrap(), 40);
+    ina.set_critical_limit(Channel::Ch1, -79).unwrap();
+    assert_eq!(ina.critical_limit(Channel::Ch1).unwrap(), -40);
+}
+
+#[test]
+fn shunt_limits_clamp() {
+    let mut ina = device();
+    ina.set_warning_limit(Channel::Ch2, i32::MAX).unwrap();
+    assert_eq!(ina.warning_limit(Channel::Ch2).unwrap(), 163_800);
+    assert_eq!(ina.destroy().register(Register::WarningLimit2), 0x7FF8);
+
+    let mut ina = device();
+    ina.set_warning_limit(Channel::Ch2, i32::MIN).unwrap();
+    assert_eq!(ina.warning_limit(Channel::Ch2).unwrap(), -163_840);
+    assert_eq!(ina.destroy().register(Register::WarningLimit2), 0x8000);
+}
+
+#[test]
+fn current_limits_use_shunt_resistor() {
+    let mut ina = device().shunt_r3(MicroOhms::from_milli_ohms(100).unwrap());
+    ina.set_critical_current_limit(Channel::Ch3, 500).unwrap();
+    assert_eq!(ina.critical_limit(Channel::Ch3).unwrap(), 50_000);
+    assert_eq!(ina.critical_current_limit(Channel::Ch3).unwrap(), 500);
+    ina.set_warning_current_limit(Channel::Ch3, -250).unwrap();
+    assert_eq!(ina.warning_limit(Channel::Ch3).unwrap(), -25_000);
+    assert_eq!(ina.warning_current_limit(Channel::Ch3).unwrap(), -250);
+}
+
+#[test]
+fn shunt_sum_limit_round_trip() {
+    let mut ina = device();
+    assert_eq!(ina.shunt_sum_limit().unwrap(), 655_320);
+    ina.set_shunt_sum_limit(100_000).unwrap();
+    assert_eq!(ina.shunt_sum_limit().unwrap(), 100_000);
+    ina.set_shunt_sum_limit(-100_039).unwrap();
+    assert_eq!(ina.shunt_sum_limit().unwrap(), -100_000);
+}
+
+#[test]
+fn shunt_sum_limit_clamp() {
+    let mut ina = device();
+    ina.set_shunt_sum_limit(i32::MAX).unwrap();
+    assert_eq!(ina.shunt_sum_limit().unwrap(), 655_320);
+    ina.set_shunt_sum_limit(i32::MIN).unwrap();
+    assert_eq!(ina.shunt_sum_limit().unwrap(), -655_360);
+    assert_eq!(
+        ina.destroy().register(Register::ShuntVoltageSumLimit),
+        0x8000
+    );
+}
+
+#[test]
+fn power_valid_limits_round_trip() {
+    let mut ina = device();
+    assert_eq!(ina.power_valid_upper_limit().unwrap(), 10_000);
+    assert_eq!(ina.power_valid_lower_limit().unwrap(), 9_000);
+    ina.set_power_valid_upper_limit(5_004).unwrap();
+    assert_eq!(ina.power_valid_upper_limit().unwrap(), 5_000);
+    ina.set_power_valid_lower_limit(i32::MAX).unwrap();
+    assert_eq!(ina.power_valid_lower_limit().unwrap(), 32_760);
+}
+
+#[test]
+fn shunt_sum_follows_summation_channels() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_shunt_voltage(Channel::Ch1, 1_000);
+    mock.set_shunt_voltage(Channel::Ch2, 2_000);
+    mock.set_shunt_voltage(Channel::Ch3, 4_000);
+    let mut ina = Ina3221::new(mock);
+    ina.set_summation_channels([true, false, true]).unwrap();
+    ina.trigger_oneshot().unwrap();
+    assert_eq!(ina.shunt_sum().unwrap(), 5_000);
+}
+
+#[test]
+fn config_bits() {
+    assert_eq!(Config::default().bits(), CONFIG_DEFAULT);
+    assert_eq!(Config::from_bits(CONFIG_DEFAULT), Config::default());
+
+    let config = Config::from_bits(0x5A5D);
+    assert_eq!(config.mode, OperatingMode::ContinuousShunt);
+    assert_eq!(config.shunt_conversion_time, ConversionTime::T588us);
+    assert_eq!(config.bus_conversion_time, ConversionTime::T204us);
+    assert_eq!(config.averaging, AveragingMode::Samples256);
+    assert_eq!(config.channels, [true, false, true]);
+    assert_eq!(config.bits(), 0x5A5D);
+}
+
+#[test]
+fn config_round_trips_through_device() {
+    let mut ina = device();
+    let mut config = Config {
+        mode: OperatingMode::ContinuousShunt,
+        averaging: AveragingMode::Samples16,
+        ..Default::default()
+    };
+    config.set_channel_enabled(Channel::Ch2, false);
+    ina.apply_config(&config).unwrap();
+    assert_eq!(ina.read_config().unwrap(), config);
+    assert_eq!(ina.measurement_interval_us().unwrap(), 16 * 2 * 1_100);
+}
+
+#[test]
+fn mask_enable_bits() {
+    assert!(MaskEnable::from_bits(1 << 14).summation(Channel::Ch1));
+    assert!(MaskEnable::from_bits(1 << 12).summation(Channel::Ch3));
+    assert!(MaskEnable::from_bits(1 << 11).warning_latch());
+    assert!(MaskEnable::from_bits(1 << 10).critical_latch());
+    assert!(MaskEnable::from_bits(1 << 9).critical(Channel::Ch1));
+    assert!(MaskEnable::from_bits(1 << 7).critical(Channel::Ch3));
+    assert!(MaskEnable::from_bits(1 << 6).summation_alert());
+    assert!(MaskEnable::from_bits(1 << 5).warning(Channel::Ch1));
+    assert!(MaskEnable::from_bits(1 << 3).warning(Channel::Ch3));
+    assert!(MaskEnable::from_bits(1 << 2).power_valid());
+    assert!(MaskEnable::from_bits(1 << 1).timing_control());
+    assert!(MaskEnable::from_bits(1).conversion_ready());
+
+    let mut mask = MaskEnable::default();
+    mask.set_summation(Channel::Ch2, true);
+    mask.set_warning_latch(true);
+    mask.set_critical_latch(true);
+    assert_eq!(mask.bits(), 0x2C00);
+    mask.set_summation(Channel::Ch2, false);
+    assert_eq!(mask.bits(), 0x0C00);
+}
+
+#[test]
+fn mask_enable_flags_are_not_written() {
+    let mut ina = device();
+    ina.set_mask_enable(MaskEnable::from_bits(0xFFFF)).unwrap();
+    assert_eq!(ina.destroy().register(Register::MaskEnable), 0x7C02);
+}
+
+#[test]
+fn reset_restores_config_cache() {
+    let mut ina = device().with_config_cache();
+    ina.set_power_mode(OperatingMode::PowerDown).unwrap();
+    ina.set_averaging_mode(AveragingMode::Samples64).unwrap();
+    assert_ne!(ina.config_cache, Some(CONFIG_DEFAULT));
+    ina.reset().unwrap();
+    assert_eq!(ina.config_cache, Some(CONFIG_DEFAULT));
+    assert_eq!(ina.read_config().unwrap(), Config::default());
+    assert_eq!(
+        ina.destroy().register(Register::Configuration),
+        CONFIG_DEFAULT
+    );
+}
+
+#[test]
+fn probe() {
+    let mut ina = device();
+    ina.probe().unwrap();
+
+    let mut ina = Ina3221::new(Ina3221Mock::new(0x41));
+    assert!(matches!(ina.probe(), Err(Error::NotFound)));
+
+    let mut mock = Ina3221Mock::default();
+    mock.set_register(Register::DieId, 0x2260);
+    let mut ina = Ina3221::new(mock);
+    assert!(matches!(
+        ina.probe(),
+        Err(Error::WrongDevice {
+            manufacturer_id: 0x5449,
+            die_id: 0x2260,
+        })
+    ));
+}
+
+#[test]
+fn measure_oneshot() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_shunt_voltage(Channel::Ch1, 10_000);
+    mock.set_bus_voltage(Channel::Ch1, 5_000);
+    mock.set_shunt_voltage(Channel::Ch3, -2_000);
+    mock.set_bus_voltage(Channel::Ch3, 3_304);
+    let mut ina = Ina3221::new(mock).shunt_r3(MicroOhms::from_milli_ohms(100).unwrap());
+
+    let measurements = ina.measure_oneshot(&mut NoDelay).unwrap();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::OneshotShuntBus);
+
+    let ch1 = measurements[Channel::Ch1];
+    assert_eq!(ch1.shunt_voltage, 10_000);
+    assert_eq!(ch1.bus_voltage, 5_000);
+    assert_eq!(ch1.current, 1_000);
+    assert_eq!(ch1.power, 5_000);
+    assert_eq!(measurements[Channel::Ch2], Default::default());
+    let ch3 = measurements[Channel::Ch3];
+    assert_eq!(ch3.shunt_voltage, -2_000);
+    assert_eq!(ch3.bus_voltage, 3_304);
+    assert_eq!(ch3.current, -20);
+    assert_eq!(ch3.power, -66);
+}
+
+#[test]
+fn energy_meter_keeps_sub_milliamp_current() {
+    let ina = device().shunt_r1(MicroOhms::from_milli_ohms(100).unwrap());
+    let mut meter = ina.energy_meter();
+    let mut measurements = Measurements::default();
+    measurements.channels[0].shunt_voltage = 80;
+    measurements.channels[0].bus_voltage = 5_000;
+    for _ in 0..3_600 {
+        meter.update(&measurements, 1_000_000);
+    }
+    assert_eq!(meter.charge_uah(Channel::Ch1), 800);
+    assert_eq!(meter.energy_uwh(Channel::Ch1), 4_000);
+    assert_eq!(meter.charge_uah(Channel::Ch2), 0);
+    assert_eq!(meter.elapsed_us(), 3_600_000_000);
+
+    meter.reset();
+    assert_eq!(meter, ina.energy_meter());
+}
+
+/// Alert pin that reads high for a number of polls, then low.
+struct AlertPin(u32);
+
+impl embedded_hal::digital::ErrorType for AlertPin {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_hal::digital::InputPin for AlertPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        let high = self.0 > 0;
+        self.0 = self.0.saturating_sub(1);
+        Ok(high)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+#[test]
+fn wait_alert() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_register(Register::CriticalLimit2, 0x0100);
+    mock.set_shunt_voltage(Channel::Ch2, 2_000);
+    let mut ina = Ina3221::new(mock);
+    let flags = ina
+        .wait_alert(&mut AlertPin(3), &mut NoDelay, None)
+        .unwrap();
+    assert!(flags.critical(Channel::Ch2));
+
+    let result = ina.wait_alert(&mut AlertPin(u32::MAX), &mut NoDelay, Some(10_000));
+    assert!(matches!(result, Err(Error::Timeout)));
+}
+
+#[test]
+fn synchronized_oneshot() {
+    let mut devices = [0x40, 0x41].map(|address| {
+        let mut mock = Ina3221Mock::new(address);
+        mock.set_shunt_voltage(Channel::Ch1, address as i32 * 400);
+        mock.set_bus_voltage(Channel::Ch2, address as i32 * 80);
+        Ina3221::new_with_addr(mock, address)
+    });
+
+    let results = crate::synchronized_oneshot(&mut devices, &mut NoDelay).unwrap();
+    for (device, (measurements, address)) in
+        devices.iter_mut().zip(results.iter().zip([0x40, 0x41]))
+    {
+        assert_eq!(device.power_mode().unwrap(), OperatingMode::OneshotShuntBus);
+        assert_eq!(measurements[Channel::Ch1].shunt_voltage, address * 400);
+        assert_eq!(measurements[Channel::Ch2].bus_voltage, address * 80);
+    }
+}
+
+#[test]
+fn typestate_transitions() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_shunt_voltage(Channel::Ch1, 1_000);
+    let mut ina = Ina3221::new(mock).into_triggered().unwrap().release();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::PowerDown);
+
+    let mut triggered = ina.into_triggered().unwrap();
+    triggered.trigger().unwrap();
+    let mut ina = triggered.release();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::OneshotShuntBus);
+
+    let mut triggered = ina.into_triggered().unwrap();
+    let measurements = triggered.measure(&mut NoDelay).unwrap();
+    assert_eq!(measurements[Channel::Ch1].shunt_voltage, 1_000);
+    let mut ina = triggered.release();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::OneshotShuntBus);
+
+    let mut ina = ina.into_continuous().unwrap().release();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::ContinuousShuntBus);
+
+    let continuous = ina.into_continuous().unwrap();
+    let mut ina = continuous.into_power_down().unwrap().release();
+    assert_eq!(ina.power_mode().unwrap(), OperatingMode::PowerDown);
+}
+
+/// Sink collecting everything written to it.
+#[cfg(feature = "embedded-io")]
+struct VecWriter(std::vec::Vec<u8>);
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::ErrorType for VecWriter {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Write for VecWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[test]
+fn write_report() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_register(Register::CriticalLimit1, 0x0100);
+    mock.set_shunt_voltage(Channel::Ch1, 10_000);
+    mock.set_bus_voltage(Channel::Ch1, 5_000);
+    let mut ina = Ina3221::new(mock);
+    let mut writer = VecWriter(std::vec::Vec::new());
+    ina.write_report(&mut writer).unwrap();
+
+    let report = std::str::from_utf8(&writer.0).unwrap();
+    let mut lines = report.lines();
+    assert_eq!(lines.next(), Some("INA3221 @ 0x40"));
+    assert_eq!(
+        lines.next(),
+        Some("config: 0x7127 avg=1, shunt CT=1.1 ms, bus CT=1.1 ms, ch1+ch2+ch3 enabled, continuous shunt+bus")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ch1: shunt=10000uV bus=5000mV current=1000mA power=5000mW")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ch2: shunt=0uV bus=0mV current=0mA power=0mW")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("ch3: shunt=0uV bus=0mV current=0mA power=0mW")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("alerts: summation none, latch warning=false critical=false, critical ch1, warning none, sum alert=false, power valid=false, timing=true, ready=true")
+    );
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn device_address() {
+    for address in [0x00, 0x3F, 0x44, 0x7F, 0xFF] {
+        let result = Ina3221::try_new_with_addr(Ina3221Mock::new(address), address);
+        assert!(matches!(result, Err(Error::InvalidAddress)));
+    }
+
+    let pins = [A0Pin::Gnd, A0Pin::Vs, A0Pin::Sda, A0Pin::Scl];
+    for (a0, address) in pins.into_iter().zip(0x40..=0x43) {
+        assert_eq!(a0.address(), address);
+        let mut ina = Ina3221::new_with_a0(Ina3221Mock::new(address), a0);
+        ina.probe().unwrap();
+        let mut ina = Ina3221::try_new_with_addr(Ina3221Mock::new(address), address).unwrap();
+        ina.probe().unwrap();
+    }
+}
+
+#[test]
+fn raw_register_access() {
+    let mut ina = device().with_config_cache();
+    ina.write_register(Register::WarningLimit3, 0x1234).unwrap();
+    assert_eq!(ina.read_register(Register::WarningLimit3).unwrap(), 0x1230);
+    assert_eq!(ina.read_register(Register::DieId).unwrap(), 0x3220);
+
+    // read-only registers are left alone
+    ina.write_register(Register::ShuntVoltage1, 0x1000).unwrap();
+    assert_eq!(ina.read_register(Register::ShuntVoltage1).unwrap(), 0);
+
+    ina.write_register(Register::Configuration, 0x4125).unwrap();
+    assert_eq!(ina.config_cache, Some(0x4125));
+    assert_eq!(ina.read_config().unwrap().channels, [true, false, false]);
+
+    ina.write_register(Register::Configuration, 0x8000).unwrap();
+    assert_eq!(ina.config_cache, Some(CONFIG_DEFAULT));
+    assert_eq!(
+        ina.read_register(Register::Configuration).unwrap(),
+        CONFIG_DEFAULT
+    );
+
+    // a raw read refreshes the cache
+    ina.i2c.set_register(Register::Configuration, 0x7007);
+    assert_eq!(ina.read_config().unwrap().bits(), CONFIG_DEFAULT);
+    assert_eq!(ina.read_register(Register::Configuration).unwrap(), 0x7007);
+    assert_eq!(ina.config_cache, Some(0x7007));
+}
+
+#[test]
+fn dump_registers() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_bus_voltage(Channel::Ch3, 12_000);
+    let mut ina = Ina3221::new(mock);
+    ina.set_power_valid_lower_limit(8_000).unwrap();
+    let dump = ina.dump_registers().unwrap();
+
+    assert_eq!(dump.get(Register::Configuration), CONFIG_DEFAULT);
+    assert_eq!(dump.get(Register::BusVoltage3), 0x2EE0);
+    assert_eq!(dump.get(Register::CriticalLimit1), 0x7FF8);
+    assert_eq!(dump.get(Register::PowerValidLowerLimit), 0x1F40);
+    assert_eq!(dump.get(Register::ManufacturerId), 0x5449);
+    assert_eq!(dump.get(Register::DieId), 0x3220);
+    assert!(dump.iter().map(|(register, _)| register).eq(Register::ALL));
+    for (register, value) in dump.iter() {
+        assert_eq!(dump.get(register), value);
+    }
+    let text = std::string::ToString::to_string(&dump);
+    assert_eq!(text.lines().nth(6), Some("0x06 BusVoltage3: 0x2ee0"));
+}
+
+#[test]
+fn conversion_time() {
+    use crate::conversion_time_us;
+
+    let longest = Some(ConversionTime::T8244us);
+    let time = conversion_time_us(AveragingMode::Samples1024, longest, longest, [true; 3]);
+    assert_eq!(time, 1024 * 3 * 2 * 8244);
+    let time = conversion_time_us(AveragingMode::Samples4, None, longest, [false, true, true]);
+    assert_eq!(time, 4 * 2 * 8244);
+    let shunt = Some(ConversionTime::T140us);
+    let time = conversion_time_us(AveragingMode::Samples1, shunt, None, [true, false, false]);
+    assert_eq!(time, 140);
+    assert_eq!(
+        conversion_time_us(AveragingMode::Samples64, None, None, [true; 3]),
+        0
+    );
+    assert_eq!(
+        conversion_time_us(AveragingMode::Samples64, shunt, shunt, [false; 3]),
+        0
+    );
+
+    let config = Config {
+        mode: OperatingMode::PowerDown,
+        ..Default::default()
+    };
+    assert_eq!(config.conversion_time_us(), 0);
+    let config = Config {
+        mode: OperatingMode::OneshotBus,
+        bus_conversion_time: ConversionTime::T332us,
+        ..Default::default()
+    };
+    assert_eq!(config.conversion_time_us(), 3 * 332);
+}
+
+#[test]
+fn power_saturates_on_tiny_shunts() {
+    let mut mock = Ina3221Mock::default();
+    mock.set_shunt_voltage(Channel::Ch1, 163_800);
+    mock.set_bus_voltage(Channel::Ch1, 26_000);
+    mock.set_shunt_voltage(Channel::Ch2, -163_840);
+    mock.set_bus_voltage(Channel::Ch2, 26_000);
+    let mut ina = Ina3221::new(mock)
+        .shunt_r1(MicroOhms::new(1).unwrap())
+        .shunt_r2(MicroOhms::new(1).unwrap());
+
+    let measurements = ina.read_all().unwrap();
+    assert_eq!(measurements[Channel::Ch1].power, i32::MAX);
+    assert_eq!(measurements[Channel::Ch2].power, i32::MIN);
+    assert_eq!(ina.power(Channel::Ch1).unwrap(), i32::MAX);
+}
@@ -0,0 +1,131 @@
+use core::marker::PhantomData;
+
+use embedded_hal::delay::DelayNs;
+
+use crate::general::OperatingMode;
+use crate::{Channel, Error, I2c, Ina3221, Measurements};
+
+/// The chip is powered down, no conversion is running.
+#[derive(Debug)]
+pub struct PowerDown;
+
+/// The chip continuously converts shunt and bus voltages.
+#[derive(Debug)]
+pub struct Continuous;
+
+/// The chip converts shunt and bus voltages once per trigger.
+#[derive(Debug)]
+pub struct Triggered;
+
+/// INA3221 with its operating mode tracked in the type.
+///
+/// Measurement registers can only be read in [`Continuous`] or [`Triggered`]
+/// mode, so reading stale data from a powered down chip is a compile-time error.
+/// Mode transitions consume the driver and return it in the new mode.
+///
+/// Use [`TypedIna3221::release`] to get back the untyped driver, e.g. to change
+/// averaging or alert limits.
+#[derive(Debug)]
+pub struct TypedIna3221<I2C, MODE> {
+    inner: Ina3221<I2C>,
+    _mode: PhantomData<MODE>,
+}
+
+fn into_mode<I2C: I2c, MODE>(
+    mut inner: Ina3221<I2C>,
+    mode: OperatingMode,
+) -> Result<TypedIna3221<I2C, MODE>, Error> {
+    inner.set_power_mode(mode)?;
+    Ok(TypedIna3221 {
+        inner,
+        _mode: PhantomData,
+    })
+}
+
+impl<I2C: I2c, MODE> TypedIna3221<I2C, MODE> {
+    /// Powers down the chip.
+    pub fn into_power_down(self) -> Result<TypedIna3221<I2C, PowerDown>, Error> {
+        into_mode(self.inner, OperatingMode::PowerDown)
+    }
+
+    /// Starts continuous shunt and bus conversions.
+    pub fn into_continuous(self) -> Result<TypedIna3221<I2C, Continuous>, Error> {
+        into_mode(self.inner, OperatingMode::ContinuousShuntBus)
+    }
+
+    /// Powers down the chip until a conversion is triggered.
+    pub fn into_triggered(self) -> Result<TypedIna3221<I2C, Triggered>, Error> {
+        into_mode(self.inner, OperatingMode::PowerDown)
+    }
+
+    /// Returns the untyped driver. The chip is left in its current mode.
+    pub fn release(self) -> Ina3221<I2C> {
+        self.inner
+    }
+}
+
+impl<I2C: I2c> TypedIna3221<I2C, Continuous> {
+    /// See [`Ina3221::read_all`].
+    pub fn read_all(&mut self) -> Result<Measurements, Error> {
+        self.inner.read_all()
+    }
+
+    /// See [`Ina3221::shunt_voltage`].
+    pub fn shunt_voltage(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.inner.shunt_voltage(channel)
+    }
+
+    /// See [`Ina3221::bus_voltage`].
+    pub fn bus_voltage(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.inner.bus_voltage(channel)
+    }
+
+    /// See [`Ina3221::current`].
+    pub fn current(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.inner.current(channel)
+    }
+
+    /// See [`Ina3221::power`].
+    pub fn power(&mut self, channel: Channel) -> Result<i32, Error> {
+        self.inner.power(channel)
+    }
+}
+
+impl<I2C: I2c> TypedIna3221<I2C, Triggered> {
+    /// See [`Ina3221::trigger_oneshot`].
+    pub fn trigger(&mut self) -> Result<(), Error> {
+        self.inner.trigger_oneshot()
+    }
+
+    /// See [`Ina3221::wait_conversion_ready`].
+    pub fn wait_conversion_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error> {
+        self.inner.wait_conversion_ready(delay)
+    }
+
+    /// Reads the result of the last triggered conversion. See [`Ina3221::read_all`].
+    pub fn read_all(&mut self) -> Result<Measurements, Error> {
+        self.inner.read_all()
+    }
+
+    /// See [`Ina3221::measure_oneshot`].
+    pub fn measure<D: DelayNs>(&mut self, delay: &mut D) -> Result<Measurements, Error> {
+        self.inner.measure_oneshot(delay)
+    }
+}
+
+impl<I2C: I2c> Ina3221<I2C> {
+    /// Powers down the chip and tracks the mode in the type.
+    pub fn into_power_down(self) -> Result<TypedIna3221<I2C, PowerDown>, Error> {
+        into_mode(self, OperatingMode::PowerDown)
+    }
+
+    /// Starts continuous conversions and tracks the mode in the type.
+    pub fn into_continuous(self) -> Result<TypedIna3221<I2C, Continuous>, Error> {
+        into_mode(self, OperatingMode::ContinuousShuntBus)
+    }
+
+    /// Powers down the chip until a conversion is triggered, and tracks the mode in the type.
+    pub fn into_triggered(self) -> Result<TypedIna3221<I2C, Triggered>, Error> {
+        into_mode(self, OperatingMode::PowerDown)
+    }
+}